@@ -1,8 +1,9 @@
 mod sourceforge_downloader;
 
 use clap::Parser;
-use log::info;
+use log::{error, info};
 use sourceforge_downloader::{SourceforgeDownloader, SourceforgeDownloaderConfig};
+use std::process;
 use tokio::join;
 
 #[derive(Parser)]
@@ -31,6 +32,30 @@ struct Cli {
     /// static file server listen address
     #[arg(long, default_value = "0.0.0.0:8080")]
     listen_addr: String,
+
+    /// 重试退避基础延迟 (毫秒)
+    #[arg(long, default_value_t = 500)]
+    retry_base_delay_ms: u64,
+    /// 重试退避倍数, 每次失败后延迟乘以该倍数
+    #[arg(long, default_value_t = 2.0)]
+    retry_multiplier: f64,
+    /// 重试退避最大延迟 (毫秒)
+    #[arg(long, default_value_t = 60_000)]
+    retry_max_delay_ms: u64,
+    /// 重试退避最大总耗时 (毫秒), 超过后不再重试
+    #[arg(long, default_value_t = 300_000)]
+    retry_max_elapsed_ms: u64,
+
+    /// 同时并发下载的文件数
+    #[arg(long, default_value_t = 2)]
+    max_concurrent_downloads: usize,
+
+    /// http/https/socks5 代理地址, 例如 socks5://127.0.0.1:1080
+    #[arg(long)]
+    proxy: Option<String>,
+    /// 不走代理的地址, 逗号分隔, 需要同时设置 --proxy 才生效
+    #[arg(long)]
+    no_proxy: Option<String>,
 }
 
 #[tokio::main]
@@ -38,6 +63,16 @@ async fn main() {
     env_logger::init();
     let cli = Cli::parse();
 
+    // 代理地址在启动时就要校验, 用 new_http_client 实际会用到的 Proxy::all
+    // 而不是单纯的 Url::parse, 否则语法合法但 scheme 不受支持的地址
+    // (例如未开启 socks 特性时的 socks5://) 会在 new_http_client 的 expect 处直接 panic
+    if let Some(proxy) = &cli.proxy {
+        if let Err(e) = reqwest::Proxy::all(proxy) {
+            error!("invalid proxy url: {:?}, err: {:?}", proxy, e);
+            process::exit(1);
+        }
+    }
+
     let sdc = SourceforgeDownloaderConfig {
         rss_url: cli.rss_url,
         user_id: cli.user_id,
@@ -47,6 +82,13 @@ async fn main() {
         domain: cli.domain,
         cron: cli.cron,
         listen_addr: cli.listen_addr,
+        retry_base_delay_ms: cli.retry_base_delay_ms,
+        retry_multiplier: cli.retry_multiplier,
+        retry_max_delay_ms: cli.retry_max_delay_ms,
+        retry_max_elapsed_ms: cli.retry_max_elapsed_ms,
+        max_concurrent_downloads: cli.max_concurrent_downloads,
+        proxy: cli.proxy,
+        no_proxy: cli.no_proxy,
     };
 
     info!("starting");
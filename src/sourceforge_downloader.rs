@@ -1,7 +1,7 @@
 use axum::Router;
 use chrono::{DateTime, Utc};
 use delay_timer::prelude::*;
-use futures_util::StreamExt;
+use futures_util::{stream, StreamExt};
 use http::header::{ACCEPT, ACCEPT_ENCODING, RANGE};
 use log::{debug, error};
 use reqwest::header::HeaderMap;
@@ -10,13 +10,15 @@ use std::{
     cmp::Ordering,
     error::Error,
     fmt::{Display, Formatter},
+    fs,
     fs::File,
     io::Write,
     path::Path,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use teloxide::prelude::*;
+use teloxide::types::MessageId;
 use tower_http::services::ServeDir;
 
 #[derive(Debug)]
@@ -87,6 +89,51 @@ pub struct SourceforgeDownloaderConfig {
     pub rss_url: String,
     pub user_id: u64,
     pub token: String,
+
+    /// 重试退避基础延迟 (毫秒)
+    pub retry_base_delay_ms: u64,
+    /// 重试退避倍数, 每次失败后延迟乘以该倍数
+    pub retry_multiplier: f64,
+    /// 重试退避最大延迟 (毫秒)
+    pub retry_max_delay_ms: u64,
+    /// 重试退避最大总耗时 (毫秒), 超过后不再重试
+    pub retry_max_elapsed_ms: u64,
+
+    /// 同时并发下载的文件数
+    pub max_concurrent_downloads: usize,
+
+    /// http/https/socks5 代理地址
+    pub proxy: Option<String>,
+    /// 不走代理的地址, 逗号分隔
+    pub no_proxy: Option<String>,
+}
+
+/// 下载失败后的指数退避参数
+#[derive(Debug, Clone, Copy)]
+struct BackoffConfig {
+    base_delay: Duration,
+    multiplier: f64,
+    max_delay: Duration,
+    max_elapsed: Duration,
+}
+
+impl BackoffConfig {
+    fn new(config: &SourceforgeDownloaderConfig) -> Self {
+        BackoffConfig {
+            base_delay: Duration::from_millis(config.retry_base_delay_ms),
+            multiplier: config.retry_multiplier,
+            max_delay: Duration::from_millis(config.retry_max_delay_ms),
+            max_elapsed: Duration::from_millis(config.retry_max_elapsed_ms),
+        }
+    }
+
+    /// 计算第 attempt 次重试前需要等待的时长, 并加入抖动避免惊群
+    fn delay_for(&self, attempt: u32) -> Duration {
+        let exp_ms = self.base_delay.as_millis() as f64 * self.multiplier.powi(attempt as i32);
+        let capped_ms = (exp_ms as u64).min(self.max_delay.as_millis() as u64);
+        let jittered_ms = capped_ms / 2 + rand::random::<u64>() % (capped_ms / 2 + 1);
+        Duration::from_millis(jittered_ms)
+    }
 }
 
 pub struct SourceforgeDownloader {
@@ -96,6 +143,7 @@ pub struct SourceforgeDownloader {
     cron: String,
 
     listen_addr: String,
+    max_concurrent_downloads: usize,
 
     inner: Arc<SourceforgeDownloaderRef>,
     delay_timer: DelayTimer,
@@ -109,10 +157,14 @@ impl SourceforgeDownloader {
             domain: config.domain.to_string(),
             cron: config.cron.to_string(),
             listen_addr: config.listen_addr.to_string(),
+            max_concurrent_downloads: config.max_concurrent_downloads,
             inner: Arc::new(SourceforgeDownloaderRef::new(
                 &config.rss_url,
                 config.user_id,
                 &config.token,
+                BackoffConfig::new(config),
+                config.proxy.as_deref(),
+                config.no_proxy.as_deref(),
             )),
             delay_timer: DelayTimer::new(),
         }
@@ -128,12 +180,13 @@ impl SourceforgeDownloader {
         axum::serve(listener, app).await.unwrap();
     }
 
-    /// 定时获取最新文件
+    /// 定时获取 rss 中所有尚未下载的文件
     pub async fn start_get_latest_file_job(&self) {
         // 避免 self 进入闭包导致 static 生命周期问题, 这里克隆一次
         let inner_clone = self.inner.clone();
         let save_dir_clone = self.save_dir.clone();
         let static_file_url_prefix = format!("{}{}", self.domain, self.assets_path);
+        let max_concurrent_downloads = self.max_concurrent_downloads;
 
         let get_latest_file_and_download = move || {
             // 再克隆一个 inner_clone 给 async 使用
@@ -142,31 +195,36 @@ impl SourceforgeDownloader {
             let static_file_url_prefix_clone = static_file_url_prefix.clone();
 
             async move {
-                // 获取最新文件
+                // 获取所有尚未下载的文件
                 match inner_clone
-                    .get_latest_file(&static_file_url_prefix_clone)
+                    .get_new_files(&static_file_url_prefix_clone, &save_dir_clone)
                     .await
                 {
-                    Ok(fmi) => {
-                        let save_path = Path::new(&save_dir_clone).join(&fmi.name);
-                        let static_file_url =
-                            format!("{}/{}", static_file_url_prefix_clone, &fmi.name);
-                        debug!(
-                            "save_path: {:?}, static file url: {}",
-                            save_path, static_file_url
-                        );
-
-                        // 下载过就不下载了
-                        if let Ok(true) = save_path.try_exists() {
-                            debug!("下载过: {:?}", save_path);
+                    Ok(files) => {
+                        if files.is_empty() {
                             return;
                         }
+                        debug!("待下载文件数: {}", files.len());
 
-                        // 启动一个新 task 来下载
+                        // 启动一个新 task, 按 pub_date 从旧到新并发下载, 不阻塞下一次 tick
                         tokio::spawn(async move {
-                            if let Err(e) = inner_clone.download_file(&save_path, &fmi).await {
-                                eprintln!("download file err: {:?}", e);
-                            }
+                            stream::iter(files.into_iter().map(|fmi| {
+                                let inner_clone = inner_clone.clone();
+                                let save_dir_clone = save_dir_clone.clone();
+                                async move {
+                                    let save_path = Path::new(&save_dir_clone).join(&fmi.name);
+                                    let mut observer = inner_clone.telegram_progress_observer();
+                                    if let Err(e) = inner_clone
+                                        .download_file(&save_path, &fmi, &mut observer)
+                                        .await
+                                    {
+                                        eprintln!("download file err: {:?}", e);
+                                    }
+                                }
+                            }))
+                            .buffer_unordered(max_concurrent_downloads)
+                            .collect::<Vec<_>>()
+                            .await;
                         });
                     }
                     Err(e) => eprintln!("error: {:?}", e),
@@ -189,64 +247,64 @@ struct SourceforgeDownloaderRef {
 
     chat_id: ChatId,
     tg_client: Bot,
+
+    backoff: BackoffConfig,
 }
 
 impl SourceforgeDownloaderRef {
-    fn new(rss_url: &str, user_id: u64, token: &str) -> Self {
+    fn new(
+        rss_url: &str,
+        user_id: u64,
+        token: &str,
+        backoff: BackoffConfig,
+        proxy: Option<&str>,
+        no_proxy: Option<&str>,
+    ) -> Self {
         SourceforgeDownloaderRef {
             rss_url: rss_url.to_string(),
-            http_client: new_http_client(),
+            http_client: new_http_client(proxy, no_proxy),
             chat_id: UserId(user_id).into(),
             tg_client: Bot::new(token),
+            backoff,
         }
     }
 
-    /// 获取最新的文件信息
-    async fn get_latest_file(
-        &self,
-        static_file_url_prefix: &str,
-    ) -> Result<FileMetaInfo, Box<dyn Error>> {
-        // 获取 rss 内容
+    /// 获取 rss 内容并解析为 channel
+    async fn fetch_channel(&self) -> Result<Channel, Box<dyn Error>> {
         let req = self.http_client.get(&self.rss_url).build()?;
         let content = self.http_client.execute(req).await?.bytes().await?;
+        Ok(Channel::read_from(&content[..])?)
+    }
+
+    /// 获取 rss 中所有尚未下载过的文件信息, 按发布时间从旧到新排序,
+    /// 避免发布较早的文件被发布较晚的文件永远挤在后面下载不到
+    async fn get_new_files(
+        &self,
+        static_file_url_prefix: &str,
+        save_dir: &str,
+    ) -> Result<Vec<FileMetaInfo>, Box<dyn Error>> {
+        let channel = self.fetch_channel().await?;
+
+        let mut files = Vec::new();
+        for item in &channel.items {
+            let fmi = match file_meta_info_from_item(item, static_file_url_prefix) {
+                Ok(fmi) => fmi,
+                Err(e) => {
+                    error!("解析 rss item 失败: {:?}", e);
+                    continue;
+                }
+            };
+
+            let save_path = Path::new(save_dir).join(&fmi.name);
+            if let Ok(true) = save_path.try_exists() {
+                debug!("下载过: {:?}", save_path);
+                continue;
+            }
+            files.push(fmi);
+        }
 
-        // 解析 rss
-        let channel = Channel::read_from(&content[..])?;
-        // 获取最新的 rom 信息
-        let latest_rom = channel.items.first().ok_or("latest rom not found")?;
-
-        // 发布日期
-        let pub_date = latest_rom.pub_date().ok_or("pub date not found")?;
-        // 下载 url
-        let download_url = latest_rom.link().ok_or("link not found")?;
-        // md5
-        let md5 = latest_rom
-            .extensions()
-            .get("media")
-            .ok_or("media not found")?
-            .get("content")
-            .ok_or("content not found")?
-            .first()
-            .ok_or("content first extension not found")?
-            .children()
-            .get("hash")
-            .ok_or("hash not found")?
-            .first()
-            .ok_or("hash first extension not found")?
-            .value()
-            .ok_or("md5 not found")?;
-        // 文件名
-        let name = Path::new(latest_rom.title().ok_or("title not found")?)
-            .file_name()
-            .ok_or("file name not found")?
-            .to_str()
-            .ok_or("file name can not to str")?;
-
-        debug!("pub_date: {:?}, md5: {:?}, name: {:?}", pub_date, md5, name);
-
-        let static_file_url = format!("{}/{}", static_file_url_prefix, name);
-        let file = FileMetaInfo::new(pub_date, download_url, md5, name, &static_file_url)?;
-        Ok(file)
+        files.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        Ok(files)
     }
 
     /// 下载文件
@@ -254,13 +312,56 @@ impl SourceforgeDownloaderRef {
         &self,
         save_path: &Path,
         file_meta_info: &FileMetaInfo,
+        observer: &mut dyn ProgressObserver,
     ) -> Result<(), Box<dyn Error>> {
         // 重试限制
         let retry_limit = 5;
         let mut retry_num = 1;
+        let download_start = Instant::now();
 
-        let mut file = File::create(save_path)?;
+        observer.on_start(file_meta_info).await;
+
+        // 预检磁盘空间: 提前知道文件大小和剩余空间, 避免下载到一半撑爆磁盘
+        // 留下会被 try_exists 误判为已下载的半成品文件
+        let expected_len = self
+            .http_client
+            .head(&file_meta_info.download_url)
+            .send()
+            .await
+            .ok()
+            .and_then(|res| res.content_length());
+
+        if let Some(expected_len) = expected_len {
+            match available_space(save_path) {
+                Ok(available) if available < expected_len => {
+                    let text = format!(
+                        "磁盘空间不足, 放弃下载: {}, 需要: {} 字节, 剩余: {} 字节",
+                        file_meta_info.name, expected_len, available
+                    );
+                    error!("{}", text);
+                    observer.on_error(&text).await;
+                    return Err(text.into());
+                }
+                Err(e) => error!("获取磁盘剩余空间失败: {:?}", e),
+                _ => {}
+            }
+        }
+
+        // 创建 (或重建) 本地文件并预分配空间, 减少碎片并让下载尽快失败而不是写到一半才报错;
+        // 每次因续传失败/md5 校验失败而重建文件时都要重新走一遍这个预分配, 否则重试会丢失该保证
+        let create_file = |save_path: &Path| -> Result<File, Box<dyn Error>> {
+            let file = File::create(save_path)?;
+            if let Some(expected_len) = expected_len {
+                if let Err(e) = file.set_len(expected_len) {
+                    error!("预分配文件空间失败: {:?}", e);
+                }
+            }
+            Ok(file)
+        };
+
+        let mut file = create_file(save_path)?;
         let mut saved_content_len = 0u64;
+        let mut md5_ctx = md5::Context::new();
 
         debug!("开始下载: {:?}", file_meta_info);
         'download_loop: loop {
@@ -270,26 +371,77 @@ impl SourceforgeDownloaderRef {
                 .get(&file_meta_info.download_url)
                 .header(RANGE, format!("bytes={}-", saved_content_len))
                 .build()?;
-            let res = self.http_client.execute(req).await?;
+            let res = match self.http_client.execute(req).await {
+                Ok(res) => res,
+                Err(e) => {
+                    let backoff_expired = download_start.elapsed() >= self.backoff.max_elapsed;
+                    if retry_num >= retry_limit || backoff_expired {
+                        observer.on_error(&e.to_string()).await;
+                        return Err(Box::new(e));
+                    }
+                    error!("连接出错: {:?}, 重试次数: {}", e, retry_num);
+                    let delay = self.backoff.delay_for(retry_num - 1);
+                    retry_num += 1;
+                    tokio::time::sleep(delay).await;
+                    continue 'download_loop;
+                }
+            };
+
+            // 只有服务端返回 206 才说明它真正接受了 Range 请求, 继续在已有文件后追加写入;
+            // 返回 200 说明服务端忽略了 Range, 此时响应体是完整文件, 必须截断重写, 否则数据会重复
+            if saved_content_len > 0 && res.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+                debug!("服务端未返回 206, 放弃断点续传, 从头写入: {:?}", save_path);
+                file = create_file(save_path)?;
+                saved_content_len = 0;
+                md5_ctx = md5::Context::new();
+            }
+
             let mut stream = res.bytes_stream();
             // 保存到本地
             while let Some(item) = stream.next().await {
                 match item {
                     Ok(chunk) => {
                         file.write_all(&chunk)?;
+                        md5_ctx.consume(&chunk);
                         saved_content_len += chunk.len() as u64;
+                        observer.on_progress(saved_content_len, expected_len).await;
                     }
                     Err(e) => {
-                        if retry_num >= retry_limit {
+                        if retry_num >= retry_limit || download_start.elapsed() >= self.backoff.max_elapsed {
+                            observer.on_error(&e.to_string()).await;
                             return Err(Box::new(e));
                         } else {
-                            error!("下载出错: {:?}, 重试次数: {}", e, retry_limit);
+                            error!("下载出错: {:?}, 重试次数: {}", e, retry_num);
+                            let delay = self.backoff.delay_for(retry_num - 1);
                             retry_num += 1;
+                            tokio::time::sleep(delay).await;
                             continue 'download_loop;
                         }
                     }
                 }
             }
+
+            // 校验 md5, rss 中的 md5 值为空时跳过校验
+            let digest = format!("{:x}", md5_ctx.compute());
+            if !md5_matches(&digest, &file_meta_info.md5) {
+                error!(
+                    "md5 校验失败, 期望: {}, 实际: {}, 文件: {:?}",
+                    file_meta_info.md5, digest, save_path
+                );
+                if retry_num >= retry_limit || download_start.elapsed() >= self.backoff.max_elapsed {
+                    let _ = fs::remove_file(save_path);
+                    observer.on_error("md5 校验失败").await;
+                    return Err(format!("md5 校验失败: {}", file_meta_info.name).into());
+                }
+                let delay = self.backoff.delay_for(retry_num - 1);
+                retry_num += 1;
+                tokio::time::sleep(delay).await;
+                file = create_file(save_path)?;
+                saved_content_len = 0;
+                md5_ctx = md5::Context::new();
+                continue 'download_loop;
+            }
+
             break 'download_loop;
         }
         debug!(
@@ -298,39 +450,243 @@ impl SourceforgeDownloaderRef {
         );
         file.flush()?;
 
-        let text = format!("下载完成: {}", file_meta_info);
-        self.send_message(&text).await;
+        observer.on_finish(&file_meta_info.static_file_url).await;
         Ok(())
     }
 
-    /// 发送 tg 消息
-    async fn send_message(&self, text: &str) {
-        if let Err(e) = self.tg_client.send_message(self.chat_id, text).await {
-            error!("send message err: {:?}", e)
+    /// 创建一个绑定到当前 telegram 会话的进度回调
+    fn telegram_progress_observer(&self) -> TelegramProgressObserver {
+        TelegramProgressObserver::new(self.tg_client.clone(), self.chat_id)
+    }
+}
+
+/// 下载进度回调, 解耦 SourceforgeDownloaderRef 与具体的进度展示方式,
+/// 便于后续接入例如本地运行时的 indicatif 进度条等其他实现
+#[async_trait::async_trait]
+trait ProgressObserver: Send {
+    /// 下载开始前调用一次
+    async fn on_start(&mut self, file_meta_info: &FileMetaInfo);
+    /// 下载过程中调用, total 在服务端未返回 Content-Length 时为 None
+    async fn on_progress(&mut self, downloaded: u64, total: Option<u64>);
+    /// 下载成功完成后调用一次
+    async fn on_finish(&mut self, static_file_url: &str);
+    /// 重试耗尽、md5 校验最终失败或磁盘空间不足而放弃下载时调用一次;
+    /// 创建/写入本地文件等本地错误直接返回, 不会调用
+    async fn on_error(&mut self, err: &str);
+}
+
+/// 下载期间最多每隔这么久更新一次 telegram 消息, 避免频繁编辑触发限流
+const PROGRESS_UPDATE_INTERVAL: Duration = Duration::from_secs(3);
+
+/// 把下载进度编辑到同一条 telegram 消息上, 而不是每次都发新消息
+struct TelegramProgressObserver {
+    tg_client: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    /// 下载开始时刻, 用于根据平均速率估算剩余时间
+    start: Instant,
+    last_sent: Instant,
+    last_percent: Option<u64>,
+}
+
+impl TelegramProgressObserver {
+    fn new(tg_client: Bot, chat_id: ChatId) -> Self {
+        TelegramProgressObserver {
+            tg_client,
+            chat_id,
+            message_id: None,
+            start: Instant::now(),
+            last_sent: Instant::now(),
+            last_percent: None,
+        }
+    }
+}
+
+/// 把秒数格式化成 mm:ss, 用于在进度消息里展示预计剩余时间
+fn format_eta(secs: u64) -> String {
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
+
+#[async_trait::async_trait]
+impl ProgressObserver for TelegramProgressObserver {
+    async fn on_start(&mut self, file_meta_info: &FileMetaInfo) {
+        let text = format!("开始下载: {}", file_meta_info.name);
+        match self.tg_client.send_message(self.chat_id, text).await {
+            Ok(msg) => self.message_id = Some(msg.id),
+            Err(e) => error!("send message err: {:?}", e),
+        }
+        self.start = Instant::now();
+        self.last_sent = Instant::now();
+    }
+
+    async fn on_progress(&mut self, downloaded: u64, total: Option<u64>) {
+        let Some(message_id) = self.message_id else {
+            return;
+        };
+        if self.last_sent.elapsed() < PROGRESS_UPDATE_INTERVAL {
+            return;
+        }
+
+        let text = match total.filter(|total| *total > 0) {
+            Some(total) => {
+                let percent = downloaded * 100 / total;
+                if self.last_percent == Some(percent) {
+                    return;
+                }
+                self.last_percent = Some(percent);
+
+                // 用从下载开始到现在的平均速率估算剩余时间, 刚开始下载时速率还不稳定就先不显示
+                let elapsed = self.start.elapsed().as_secs_f64();
+                let eta = if elapsed > 0.0 && downloaded > 0 && downloaded < total {
+                    let speed = downloaded as f64 / elapsed;
+                    let remaining_secs = (total - downloaded) as f64 / speed;
+                    format!(", 剩余 {}", format_eta(remaining_secs as u64))
+                } else {
+                    String::new()
+                };
+
+                format!(
+                    "下载中: {}% ({} / {} 字节){}",
+                    percent, downloaded, total, eta
+                )
+            }
+            None => format!("下载中: {} 字节", downloaded),
+        };
+
+        self.last_sent = Instant::now();
+        if let Err(e) = self
+            .tg_client
+            .edit_message_text(self.chat_id, message_id, text)
+            .await
+        {
+            error!("edit message err: {:?}", e)
+        }
+    }
+
+    async fn on_finish(&mut self, static_file_url: &str) {
+        let Some(message_id) = self.message_id else {
+            return;
+        };
+        let text = format!("下载完成: {}", static_file_url);
+        if let Err(e) = self
+            .tg_client
+            .edit_message_text(self.chat_id, message_id, text)
+            .await
+        {
+            error!("edit message err: {:?}", e)
+        }
+    }
+
+    async fn on_error(&mut self, err: &str) {
+        let Some(message_id) = self.message_id else {
+            return;
+        };
+        let text = format!("下载失败: {}", err);
+        if let Err(e) = self
+            .tg_client
+            .edit_message_text(self.chat_id, message_id, text)
+            .await
+        {
+            error!("edit message err: {:?}", e)
         }
     }
 }
 
-/// 创建 http 客户端
-fn new_http_client() -> reqwest::Client {
+/// 校验实际计算出的 md5 是否匹配期望值, expected 为空 (rss 中 hash 的值为空) 时视为跳过校验
+fn md5_matches(digest: &str, expected: &str) -> bool {
+    expected.is_empty() || digest == expected
+}
+
+/// 从一条 rss item 中解析出文件信息
+fn file_meta_info_from_item(
+    item: &rss::Item,
+    static_file_url_prefix: &str,
+) -> Result<FileMetaInfo, Box<dyn Error>> {
+    // 发布日期
+    let pub_date = item.pub_date().ok_or("pub date not found")?;
+    // 下载 url
+    let download_url = item.link().ok_or("link not found")?;
+    // md5
+    let md5 = item
+        .extensions()
+        .get("media")
+        .ok_or("media not found")?
+        .get("content")
+        .ok_or("content not found")?
+        .first()
+        .ok_or("content first extension not found")?
+        .children()
+        .get("hash")
+        .ok_or("hash not found")?
+        .first()
+        .ok_or("hash first extension not found")?
+        .value()
+        .ok_or("md5 not found")?;
+    // 文件名
+    let name = Path::new(item.title().ok_or("title not found")?)
+        .file_name()
+        .ok_or("file name not found")?
+        .to_str()
+        .ok_or("file name can not to str")?;
+
+    debug!("pub_date: {:?}, md5: {:?}, name: {:?}", pub_date, md5, name);
+
+    let static_file_url = format!("{}/{}", static_file_url_prefix, name);
+    FileMetaInfo::new(pub_date, download_url, md5, name, &static_file_url)
+}
+
+/// 创建 http 客户端, proxy 支持 http:// https:// socks5:// 协议
+fn new_http_client(proxy: Option<&str>, no_proxy: Option<&str>) -> reqwest::Client {
     let mut header_map = HeaderMap::new();
     header_map.insert(ACCEPT, "*/*".parse().unwrap());
     header_map.insert(ACCEPT_ENCODING, "identity".parse().unwrap());
 
-    reqwest::ClientBuilder::new()
+    let mut builder = reqwest::ClientBuilder::new()
         .connect_timeout(Duration::from_secs(10))
         .cookie_store(true)
         .user_agent("Wget/1.21.4")
-        .default_headers(header_map)
-        .build()
-        .unwrap()
+        .default_headers(header_map);
+
+    if let Some(proxy) = proxy {
+        // main 已经用同一个 reqwest::Proxy::all 校验过这个地址, 这里不会失败
+        let mut p = reqwest::Proxy::all(proxy).expect("invalid proxy url");
+        if let Some(no_proxy) = no_proxy {
+            p = p.no_proxy(reqwest::NoProxy::from_string(no_proxy));
+        }
+        builder = builder.proxy(p);
+    }
+
+    builder.build().unwrap()
+}
+
+/// 查询 path 所在文件系统的剩余可用空间 (字节)
+#[cfg(unix)]
+fn available_space(path: &Path) -> Result<u64, Box<dyn Error>> {
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let stat = nix::sys::statvfs::statvfs(dir)?;
+    Ok(stat.blocks_available() * stat.fragment_size())
+}
+
+/// 查询 path 所在文件系统的剩余可用空间 (字节)
+#[cfg(not(unix))]
+fn available_space(path: &Path) -> Result<u64, Box<dyn Error>> {
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    Ok(fs2::available_space(dir)?)
 }
 
 #[cfg(test)]
 mod tests {
     use crate::sourceforge_downloader::{
-        SourceforgeDownloader, SourceforgeDownloaderConfig, SourceforgeDownloaderRef,
+        file_meta_info_from_item, md5_matches, BackoffConfig, SourceforgeDownloader,
+        SourceforgeDownloaderConfig, SourceforgeDownloaderRef,
     };
+    use rss::Channel;
     use std::path::Path;
     use std::{env, time::Duration};
     use tokio::time::sleep;
@@ -353,21 +709,114 @@ mod tests {
                 .to_string(),
             user_id,
             token: token.to_string(),
+            retry_base_delay_ms: 500,
+            retry_multiplier: 2.0,
+            retry_max_delay_ms: 60_000,
+            retry_max_elapsed_ms: 300_000,
+            max_concurrent_downloads: 2,
+            proxy: None,
+            no_proxy: None,
         }
     }
 
+    fn get_backoff_config() -> BackoffConfig {
+        BackoffConfig::new(&get_sourceforge_downloader_config())
+    }
+
+    #[test]
+    fn delay_for_starts_at_base_delay_and_grows_exponentially() {
+        let backoff = get_backoff_config();
+
+        // 第一次重试 (attempt = 0) 的延迟以 base_delay 为上限 (抖动后落在 base_delay/2 到 base_delay 之间),
+        // 不应该已经乘过一次 multiplier
+        let first = backoff.delay_for(0);
+        assert!(
+            first <= backoff.base_delay,
+            "第一次重试延迟 {:?} 不应超过 base_delay {:?}",
+            first,
+            backoff.base_delay
+        );
+
+        // 后续延迟应该随着 attempt 增大而增大 (含抖动的上界也应递增), 但不会超过 max_delay
+        let later = backoff.delay_for(4);
+        assert!(later > first);
+        assert!(later <= backoff.max_delay);
+    }
+
+    #[test]
+    fn delay_for_caps_at_max_delay() {
+        let backoff = get_backoff_config();
+
+        let delay = backoff.delay_for(20);
+        assert!(delay <= backoff.max_delay);
+    }
+
+    #[test]
+    fn md5_matches_accepts_matching_digest() {
+        assert!(md5_matches("abc123", "abc123"));
+    }
+
+    #[test]
+    fn md5_matches_rejects_mismatching_digest() {
+        assert!(!md5_matches("abc123", "def456"));
+    }
+
+    #[test]
+    fn md5_matches_skips_check_when_expected_is_empty() {
+        assert!(md5_matches("abc123", ""));
+    }
+
+    #[test]
+    fn file_meta_info_from_item_parses_rss_item() {
+        let rss = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:media="http://search.yahoo.com/mrss/">
+  <channel>
+    <title>bettercap.mirror</title>
+    <link>https://sourceforge.net/projects/bettercap.mirror/</link>
+    <description>bettercap.mirror</description>
+    <item>
+      <title>/v2.32.0/bettercap_linux_amd64_v2.32.0.zip</title>
+      <link>https://downloads.sourceforge.net/project/bettercap.mirror/v2.32.0/bettercap_linux_amd64_v2.32.0.zip</link>
+      <pubDate>Sat, 01 Jul 2023 12:00:00 +0000</pubDate>
+      <media:content url="https://downloads.sourceforge.net/project/bettercap.mirror/v2.32.0/bettercap_linux_amd64_v2.32.0.zip" filesize="1234">
+        <media:hash algo="md5">d41d8cd98f00b204e9800998ecf8427e</media:hash>
+      </media:content>
+    </item>
+  </channel>
+</rss>"#;
+
+        let channel = Channel::read_from(rss.as_bytes()).unwrap();
+        let item = &channel.items()[0];
+
+        let fmi = file_meta_info_from_item(item, "https://example.com/assets").unwrap();
+
+        assert_eq!(fmi.name, "bettercap_linux_amd64_v2.32.0.zip");
+        assert_eq!(
+            fmi.download_url,
+            "https://downloads.sourceforge.net/project/bettercap.mirror/v2.32.0/bettercap_linux_amd64_v2.32.0.zip"
+        );
+        assert_eq!(fmi.md5, "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(
+            fmi.static_file_url,
+            "https://example.com/assets/bettercap_linux_amd64_v2.32.0.zip"
+        );
+    }
+
     #[tokio::test]
-    async fn get_latest_file() {
+    async fn get_new_files() {
         setup();
 
         let sdl = SourceforgeDownloaderRef::new(
             "https://sourceforge.net/projects/evolution-x/rss?path=/raphael/14",
             123,
             "hello",
+            get_backoff_config(),
+            None,
+            None,
         );
-        match sdl.get_latest_file("").await {
-            Ok(file) => {
-                println!("{:?}", file)
+        match sdl.get_new_files("", "/tmp").await {
+            Ok(files) => {
+                println!("{:?}", files)
             }
             Err(e) => {
                 eprintln!("{:?}", e)
@@ -386,28 +835,30 @@ mod tests {
             "https://sourceforge.net/projects/bettercap.mirror/rss?path=/v2.32.0",
             user_id,
             &token,
+            get_backoff_config(),
+            None,
+            None,
         );
-        let file_meta_info = sdl.get_latest_file("").await.unwrap();
+        let file_meta_info = sdl
+            .get_new_files("", "/tmp")
+            .await
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap();
         let save_path = Path::new("/tmp").join(&file_meta_info.name);
 
         println!("download_url: {:?}", &file_meta_info.download_url);
 
-        if let Err(e) = sdl.download_file(&save_path, &file_meta_info).await {
+        let mut observer = sdl.telegram_progress_observer();
+        if let Err(e) = sdl
+            .download_file(&save_path, &file_meta_info, &mut observer)
+            .await
+        {
             eprintln!("{:?}", e)
         }
     }
 
-    #[tokio::test]
-    async fn test_send_message() {
-        setup();
-
-        let user_id = env::var("USER_ID").unwrap().parse::<u64>().unwrap();
-        let token = env::var("TELOXIDE_TOKEN").unwrap();
-
-        let sdl = SourceforgeDownloaderRef::new("", user_id, token.as_str());
-        sdl.send_message("hello world").await
-    }
-
     #[tokio::test]
     async fn file_server() {
         setup();